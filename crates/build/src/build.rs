@@ -0,0 +1,190 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use crate::{
+    command::build_command,
+    manifest::{hash_file_sha256, verify_manifest, write_manifest, BuildManifest},
+    output::{BuildEvent, OutputFormat},
+    utils::{cargo_release_dir, resolve_cargo_lock_path, resolve_package_name, resolve_toolchain},
+    BuildArgs, BuildScriptOpts, BUILD_TARGET,
+};
+
+/// Emits `event` as a newline-delimited JSON line on stdout when `format` is
+/// [`OutputFormat::Json`]; a no-op in [`OutputFormat::Text`] mode, which instead relies on the
+/// inherited stdout/stderr of the underlying `cargo`/`docker` process for human-oriented logs.
+fn emit(format: OutputFormat, event: &BuildEvent) {
+    if format == OutputFormat::Json {
+        print!("{}", event.to_ndjson_line());
+    }
+}
+
+/// Reports a build failure: as a `BuildEvent::Error` in JSON mode, or an eprintln in text mode
+/// (unless `quiet`), then panics either way so `build_program_internal` keeps its "panic on
+/// failure" contract for build-script callers.
+fn fail(format: OutputFormat, quiet: bool, code: &str, message: String) -> ! {
+    emit(format, &BuildEvent::Error { code: code.to_string(), message: message.clone() });
+    if format == OutputFormat::Text && !quiet {
+        eprintln!("[sp1] build failed ({code}): {message}");
+    }
+    panic!("{message}");
+}
+
+/// Resolves the ELF path cargo will produce for `args` inside `program_dir`'s
+/// [`crate::HELPER_TARGET_SUBDIR`].
+///
+/// cargo names the binary after `--bin` when given, or otherwise after the package itself, so the
+/// fallback is resolved from `program_dir`'s `Cargo.toml` rather than a literal guess.
+fn built_elf_path(program_dir: &Path, args: &BuildArgs) -> PathBuf {
+    let binary_name =
+        if !args.binary.is_empty() { args.binary.clone() } else { resolve_package_name(program_dir) };
+    cargo_release_dir(program_dir).join(binary_name)
+}
+
+/// Resolves the ELF path the built binary is copied to: `elf_name` (or the binary/package name)
+/// inside `output_directory`.
+fn output_elf_path(program_dir: &Path, args: &BuildArgs) -> PathBuf {
+    let name = if !args.elf_name.is_empty() {
+        args.elf_name.clone()
+    } else if !args.binary.is_empty() {
+        args.binary.clone()
+    } else {
+        resolve_package_name(program_dir)
+    };
+    program_dir.join(&args.output_directory).join(name)
+}
+
+/// Builds the program if the program at the specified path, or one of its dependencies, changes.
+///
+/// When `opts.args.verify_manifest` is set, no build is performed; instead the ELF already at the
+/// expected output location and the nearest `Cargo.lock` above `path` are re-hashed and checked
+/// against the `<elf_name>.manifest.json` recorded by a prior build (see
+/// [`crate::verify_manifest`]).
+///
+/// Otherwise, this runs `cargo build` (or a `docker run` wrapping it, per `opts.args.docker`),
+/// copies the resulting ELF to `opts.args.output_directory`, and writes a [`BuildManifest`]
+/// alongside it recording the ELF's hash, the resolved toolchain, the target triple, the
+/// feature set, the Docker tag (if any), and the `Cargo.lock` hash.
+///
+/// When `opts.args.format` is [`OutputFormat::Json`], each stage is additionally reported as a
+/// newline-delimited [`BuildEvent`] on stdout: [`BuildEvent::Start`], a [`BuildEvent::Artifact`]
+/// for the compiled ELF, a final [`BuildEvent::Elf`] with its content hash, or a
+/// [`BuildEvent::Error`] with a stable code if any stage fails.
+///
+/// Set the `SP1_SKIP_PROGRAM_BUILD` environment variable to `true` to skip building the program.
+pub fn build_program_internal(path: &str, opts: BuildScriptOpts) {
+    if std::env::var("SP1_SKIP_PROGRAM_BUILD").as_deref() == Ok("true") {
+        return;
+    }
+
+    let BuildScriptOpts { args, quiet } = opts;
+    let format = args.format;
+    let program_dir = PathBuf::from(path);
+
+    if args.verify_manifest {
+        let elf_path = output_elf_path(&program_dir, &args);
+        let cargo_lock_path = match resolve_cargo_lock_path(&program_dir) {
+            Some(path) => path,
+            None => fail(
+                format,
+                quiet,
+                "cargo_lock_not_found",
+                format!("no Cargo.lock found above {}", program_dir.display()),
+            ),
+        };
+        return match verify_manifest(&elf_path, &cargo_lock_path) {
+            Ok(()) => {
+                if format == OutputFormat::Text && !quiet {
+                    println!("[sp1] build manifest verified for {}", elf_path.display());
+                }
+            }
+            Err(err) => fail(format, quiet, "manifest_verification_failed", err.to_string()),
+        };
+    }
+
+    emit(format, &BuildEvent::Start { program: path.to_string() });
+    if format == OutputFormat::Text && !quiet {
+        println!("[sp1] building program at {}", program_dir.display());
+    }
+
+    let mut command = build_command(&args, &program_dir);
+    command.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+
+    let status = match command.status() {
+        Ok(status) => status,
+        Err(err) => fail(format, quiet, "cargo_spawn_failed", err.to_string()),
+    };
+    if !status.success() {
+        fail(format, quiet, "cargo_build_failed", format!("cargo build exited with {status:?}"));
+    }
+
+    let source_elf_path = built_elf_path(&program_dir, &args);
+    let elf_path = output_elf_path(&program_dir, &args);
+    if let Some(parent) = elf_path.parent() {
+        if let Err(err) = fs::create_dir_all(parent) {
+            fail(format, quiet, "output_dir_creation_failed", err.to_string());
+        }
+    }
+    if let Err(err) = fs::copy(&source_elf_path, &elf_path) {
+        fail(
+            format,
+            quiet,
+            "elf_copy_failed",
+            format!(
+                "failed to copy ELF from {} to {}: {err}",
+                source_elf_path.display(),
+                elf_path.display()
+            ),
+        );
+    }
+    emit(format, &BuildEvent::Artifact { path: elf_path.display().to_string() });
+
+    let elf_sha256 = match hash_file_sha256(&elf_path) {
+        Ok(hash) => hash,
+        Err(err) => fail(format, quiet, "elf_hash_failed", err.to_string()),
+    };
+    emit(format, &BuildEvent::Elf { path: elf_path.display().to_string(), sha256: elf_sha256.clone() });
+    if format == OutputFormat::Text && !quiet {
+        println!("[sp1] program built and copied to {}", elf_path.display());
+    }
+
+    let cargo_lock_path = match resolve_cargo_lock_path(&program_dir) {
+        Some(path) => path,
+        None => fail(
+            format,
+            quiet,
+            "cargo_lock_not_found",
+            format!("no Cargo.lock found above {}", program_dir.display()),
+        ),
+    };
+    let cargo_lock_sha256 = match hash_file_sha256(&cargo_lock_path) {
+        Ok(hash) => hash,
+        Err(err) => fail(format, quiet, "cargo_lock_hash_failed", err.to_string()),
+    };
+    let mut features = args.features.clone();
+    features.sort();
+
+    let manifest = BuildManifest {
+        elf_sha256,
+        toolchain: resolve_toolchain(),
+        target: BUILD_TARGET.to_string(),
+        features,
+        docker_tag: args.docker.then(|| args.tag.clone()),
+        cargo_lock_sha256,
+    };
+    if let Err(err) = write_manifest(&elf_path, &manifest) {
+        fail(format, quiet, "manifest_write_failed", err.to_string());
+    }
+}
+
+/// Entry point used by the `cargo prove build` CLI to build a program from parsed [`BuildArgs`]
+/// in the current directory.
+pub fn execute_build_program(args: &BuildArgs) {
+    let program_dir = std::env::current_dir().expect("failed to read current directory");
+    build_program_internal(
+        program_dir.to_str().expect("program directory is not valid UTF-8"),
+        BuildScriptOpts { args: args.clone(), quiet: false },
+    );
+}
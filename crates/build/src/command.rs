@@ -0,0 +1,28 @@
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use crate::{utils::cargo_build_args, BuildArgs};
+
+/// Builds (but does not run) the command used to compile the program at `program_dir`, either a
+/// local `cargo build` or, when `args.docker` is set, a `docker run` wrapping the same `cargo`
+/// invocation inside the `ghcr.io/succinctlabs/sp1` image for reproducible builds.
+pub(crate) fn build_command(args: &BuildArgs, program_dir: &Path) -> Command {
+    if args.docker {
+        let mut command = Command::new("docker");
+        command
+            .args(["run", "--rm", "-v"])
+            .arg(format!("{}:/root/program", program_dir.display()))
+            .args(["-w", "/root/program"])
+            .arg(format!("ghcr.io/succinctlabs/sp1:{}", args.tag))
+            .arg("cargo")
+            .args(cargo_build_args(args))
+            .stdin(Stdio::inherit());
+        command
+    } else {
+        let mut command = Command::new("cargo");
+        command.args(cargo_build_args(args)).current_dir(program_dir).stdin(Stdio::inherit());
+        command
+    }
+}
@@ -1,8 +1,12 @@
 mod build;
 mod command;
+mod manifest;
+mod output;
 mod utils;
 use build::build_program_internal;
 pub use build::execute_build_program;
+pub use manifest::{verify_manifest, BuildManifest, ManifestError};
+pub use output::{BuildEvent, OutputFormat};
 
 use clap::Parser;
 
@@ -16,6 +20,11 @@ const HELPER_TARGET_SUBDIR: &str = "elf-compilation";
 /// Additional arguments are useful for configuring the build process, including options for using
 /// Docker, specifying binary and ELF names, ignoring Rust version checks, and enabling specific
 /// features.
+///
+/// After a successful build, `build_program_internal` writes a [`BuildManifest`] next to the
+/// output ELF recording the ELF's hash, the resolved toolchain and target, the feature set, the
+/// Docker tag (if any), and the `Cargo.lock` hash. Pass `--verify-manifest` to instead check an
+/// existing ELF and `Cargo.lock` against that manifest via [`verify_manifest`].
 #[derive(Clone, Parser, Debug)]
 pub struct BuildArgs {
     #[clap(
@@ -61,6 +70,25 @@ pub struct BuildArgs {
         default_value = DEFAULT_OUTPUT_DIR
     )]
     pub output_directory: String,
+    /// When set to [`OutputFormat::Json`], `build_program_internal` emits a stream of
+    /// [`BuildEvent`]s as newline-delimited JSON on stdout instead of free-form log lines, so
+    /// build scripts and wrapper tools can consume build start, each compiled artifact, the
+    /// final ELF (with its content hash), and any error deterministically. This coexists with
+    /// [`BuildScriptOpts::quiet`]: quiet only suppresses human-oriented informational output and
+    /// has no effect once JSON output is selected.
+    #[clap(
+        long,
+        value_enum,
+        help = "Output format for build progress and results",
+        default_value = "text"
+    )]
+    pub format: OutputFormat,
+    #[clap(
+        long,
+        action,
+        help = "Verify the ELF and Cargo.lock against the recorded build manifest instead of building"
+    )]
+    pub verify_manifest: bool,
 }
 
 // Implement default args to match clap defaults.
@@ -76,6 +104,8 @@ impl Default for BuildArgs {
             output_directory: DEFAULT_OUTPUT_DIR.to_string(),
             locked: false,
             no_default_features: false,
+            format: OutputFormat::Text,
+            verify_manifest: false,
         }
     }
 }
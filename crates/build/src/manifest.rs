@@ -0,0 +1,191 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// A manifest recording the provenance of a built SP1 guest ELF, written alongside the ELF as
+/// `<elf_name>.manifest.json`.
+///
+/// Combined with `--docker` and `--locked`, this lets downstream consumers prove that a given
+/// proving key corresponds to an audited, byte-identical guest binary, rather than trusting that
+/// a rebuild reproduced the same bytes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildManifest {
+    /// The SHA-256 hash of the ELF's contents, hex-encoded.
+    pub elf_sha256: String,
+    /// The Rust toolchain as reported by the host's `cargo --version`. For `--docker` builds this
+    /// is the host's cargo, not the one inside the container that compiled the ELF.
+    pub toolchain: String,
+    /// The compilation target triple (always [`crate::BUILD_TARGET`] for SP1 guests).
+    pub target: String,
+    /// The feature set the ELF was built with, sorted for determinism.
+    pub features: Vec<String>,
+    /// The `ghcr.io/succinctlabs/sp1` Docker tag used, if the build ran with `--docker`.
+    pub docker_tag: Option<String>,
+    /// The SHA-256 hash of the `Cargo.lock` that pinned dependency versions for this build.
+    pub cargo_lock_sha256: String,
+}
+
+/// An error encountered while writing, reading, or verifying a [`BuildManifest`].
+#[derive(Debug)]
+pub enum ManifestError {
+    /// Reading or writing the manifest or one of the files it describes failed.
+    Io(io::Error),
+    /// The manifest file's contents could not be parsed as JSON.
+    Serde(serde_json::Error),
+    /// A hash recorded in the manifest no longer matches the file on disk.
+    Mismatch { field: &'static str, expected: String, actual: String },
+}
+
+impl std::fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read or write build manifest: {err}"),
+            Self::Serde(err) => write!(f, "failed to parse build manifest: {err}"),
+            Self::Mismatch { field, expected, actual } => write!(
+                f,
+                "build manifest mismatch on `{field}`: expected {expected}, found {actual}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+impl From<io::Error> for ManifestError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for ManifestError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Serde(err)
+    }
+}
+
+/// Returns the manifest path for a given ELF path: `<elf_name>.manifest.json` next to the ELF.
+pub fn manifest_path_for(elf_path: &Path) -> PathBuf {
+    let file_name = format!("{}.manifest.json", elf_path.file_name().unwrap_or_default().to_string_lossy());
+    elf_path.with_file_name(file_name)
+}
+
+/// Hashes a file's contents with SHA-256, returning the hex-encoded digest.
+pub fn hash_file_sha256(path: &Path) -> Result<String, ManifestError> {
+    let bytes = fs::read(path)?;
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes);
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Writes `manifest` to `<elf_name>.manifest.json` next to `elf_path`.
+pub fn write_manifest(elf_path: &Path, manifest: &BuildManifest) -> Result<(), ManifestError> {
+    let json = serde_json::to_string_pretty(manifest)?;
+    fs::write(manifest_path_for(elf_path), json)?;
+    Ok(())
+}
+
+/// Reads the manifest recorded for `elf_path`.
+pub fn read_manifest(elf_path: &Path) -> Result<BuildManifest, ManifestError> {
+    let json = fs::read_to_string(manifest_path_for(elf_path))?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Verifies that the ELF at `elf_path` and the `Cargo.lock` at `cargo_lock_path` still match the
+/// manifest recorded for `elf_path`, by re-hashing both rather than trusting cached values.
+///
+/// Returns `Ok(())` if every recorded hash still matches, so callers can treat the ELF as
+/// byte-identical to the audited build the manifest describes.
+pub fn verify_manifest(elf_path: &Path, cargo_lock_path: &Path) -> Result<(), ManifestError> {
+    let manifest = read_manifest(elf_path)?;
+
+    let elf_sha256 = hash_file_sha256(elf_path)?;
+    if elf_sha256 != manifest.elf_sha256 {
+        return Err(ManifestError::Mismatch {
+            field: "elf_sha256",
+            expected: manifest.elf_sha256,
+            actual: elf_sha256,
+        });
+    }
+
+    let cargo_lock_sha256 = hash_file_sha256(cargo_lock_path)?;
+    if cargo_lock_sha256 != manifest.cargo_lock_sha256 {
+        return Err(ManifestError::Mismatch {
+            field: "cargo_lock_sha256",
+            expected: manifest.cargo_lock_sha256,
+            actual: cargo_lock_sha256,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(elf_sha256: &str, cargo_lock_sha256: &str) -> BuildManifest {
+        BuildManifest {
+            elf_sha256: elf_sha256.to_string(),
+            toolchain: "cargo 1.79.0 (test)".to_string(),
+            target: "riscv32im-succinct-zkvm-elf".to_string(),
+            features: vec![],
+            docker_tag: None,
+            cargo_lock_sha256: cargo_lock_sha256.to_string(),
+        }
+    }
+
+    /// A scratch directory unique to this test run, so parallel tests don't collide.
+    fn unique_temp_dir(label: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("sp1-manifest-test-{label}-{:?}", std::thread::current().id()))
+    }
+
+    #[test]
+    fn manifest_path_sits_next_to_elf() {
+        let path = manifest_path_for(Path::new("/tmp/elf/my-program"));
+        assert_eq!(path, Path::new("/tmp/elf/my-program.manifest.json"));
+    }
+
+    #[test]
+    fn hash_is_stable_for_the_same_contents() {
+        let dir = unique_temp_dir("hash-stable");
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("artifact.bin");
+        fs::write(&file, b"hello sp1").unwrap();
+
+        let first = hash_file_sha256(&file).unwrap();
+        let second = hash_file_sha256(&file).unwrap();
+        assert_eq!(first, second);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn verify_manifest_round_trips_and_detects_tampering() {
+        let dir = unique_temp_dir("verify-round-trip");
+        fs::create_dir_all(&dir).unwrap();
+        let elf_path = dir.join("program");
+        let cargo_lock_path = dir.join("Cargo.lock");
+        fs::write(&elf_path, b"elf bytes").unwrap();
+        fs::write(&cargo_lock_path, b"lock bytes").unwrap();
+
+        let manifest = manifest(
+            &hash_file_sha256(&elf_path).unwrap(),
+            &hash_file_sha256(&cargo_lock_path).unwrap(),
+        );
+        write_manifest(&elf_path, &manifest).unwrap();
+        assert!(verify_manifest(&elf_path, &cargo_lock_path).is_ok());
+
+        fs::write(&elf_path, b"tampered elf bytes").unwrap();
+        assert!(matches!(
+            verify_manifest(&elf_path, &cargo_lock_path),
+            Err(ManifestError::Mismatch { field: "elf_sha256", .. })
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
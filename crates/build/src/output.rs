@@ -0,0 +1,59 @@
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// The output format used when reporting build progress and results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-oriented log lines (the default).
+    #[default]
+    Text,
+    /// Newline-delimited JSON events, one per line, suitable for build scripts and CI tooling
+    /// to consume deterministically instead of scraping stdout.
+    Json,
+}
+
+/// A single structured event emitted while building an SP1 program.
+///
+/// When [`OutputFormat::Json`] is selected, `build_program_internal` emits a stream of these as
+/// newline-delimited JSON instead of free-form log lines. A build's stream always starts with
+/// [`BuildEvent::Start`] and ends with either a final [`BuildEvent::Elf`] or a
+/// [`BuildEvent::Error`]. With `--verify-manifest` no build runs and no [`BuildEvent::Start`] is
+/// emitted: the stream is a single [`BuildEvent::Error`] on failure and empty on success.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum BuildEvent {
+    /// Emitted once, before compilation starts.
+    Start {
+        /// The path to the program being built, as passed to `build_program`.
+        program: String,
+    },
+    /// Emitted once the compiled ELF has been copied to its output location, just before the
+    /// [`BuildEvent::Elf`] event for that same path.
+    Artifact {
+        /// The path to the compiled artifact.
+        path: String,
+    },
+    /// Emitted once the final ELF has been copied to its output location.
+    Elf {
+        /// The path to the final ELF.
+        path: String,
+        /// The SHA-256 hash of the ELF's contents, hex-encoded.
+        sha256: String,
+    },
+    /// Emitted in place of the text-mode error log line when the build fails, just before the
+    /// build panics.
+    Error {
+        /// A stable, machine-matchable error code (e.g. `"cargo_build_failed"`).
+        code: String,
+        /// A human-readable description of the failure.
+        message: String,
+    },
+}
+
+impl BuildEvent {
+    /// Serializes this event as a single line of newline-delimited JSON, including the
+    /// trailing newline.
+    pub fn to_ndjson_line(&self) -> String {
+        format!("{}\n", serde_json::to_string(self).expect("BuildEvent is always serializable"))
+    }
+}
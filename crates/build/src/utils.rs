@@ -0,0 +1,112 @@
+use std::process::{Command, Stdio};
+
+use crate::{BuildArgs, BUILD_TARGET, HELPER_TARGET_SUBDIR};
+
+/// Builds the `cargo build` argument list implied by `args`, shared by the local and Docker
+/// build paths.
+///
+/// Passes `--target-dir` set to the relative [`HELPER_TARGET_SUBDIR`] so the build always lands
+/// at the path [`cargo_release_dir`] expects, rather than cargo's own default `target/` dir. The
+/// command's working directory is `program_dir` both locally and inside the Docker container (see
+/// [`crate::command::build_command`]), so the relative path resolves to the same place either way.
+pub(crate) fn cargo_build_args(args: &BuildArgs) -> Vec<String> {
+    let mut build_args = vec![
+        "build".to_string(),
+        "--release".to_string(),
+        "--target".to_string(),
+        BUILD_TARGET.to_string(),
+        "--target-dir".to_string(),
+        HELPER_TARGET_SUBDIR.to_string(),
+    ];
+
+    if !args.binary.is_empty() {
+        build_args.push("--bin".to_string());
+        build_args.push(args.binary.clone());
+    }
+
+    if !args.features.is_empty() {
+        build_args.push("--features".to_string());
+        build_args.push(args.features.join(","));
+    }
+
+    if args.no_default_features {
+        build_args.push("--no-default-features".to_string());
+    }
+
+    if args.ignore_rust_version {
+        build_args.push("--ignore-rust-version".to_string());
+    }
+
+    if args.locked {
+        build_args.push("--locked".to_string());
+    }
+
+    build_args
+}
+
+/// The directory cargo places release artifacts for [`BUILD_TARGET`] in, for a program built at
+/// `program_dir` via [`HELPER_TARGET_SUBDIR`].
+pub(crate) fn cargo_release_dir(program_dir: &std::path::Path) -> std::path::PathBuf {
+    program_dir.join(HELPER_TARGET_SUBDIR).join(BUILD_TARGET).join("release")
+}
+
+/// Resolves the name cargo gives the compiled binary for the package at `program_dir`, by reading
+/// the `name` key out of its `Cargo.toml`'s `[package]` table.
+///
+/// Falls back to `"program"` if the manifest can't be read or has no `[package]` name, since a
+/// missing package name shouldn't prevent the build itself from completing (the resulting copy
+/// failure will surface its own clear error instead).
+pub(crate) fn resolve_package_name(program_dir: &std::path::Path) -> String {
+    let Ok(manifest) = std::fs::read_to_string(program_dir.join("Cargo.toml")) else {
+        return "program".to_string();
+    };
+
+    let mut in_package_table = false;
+    for line in manifest.lines() {
+        let line = line.trim();
+        if let Some(table) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            in_package_table = table == "package";
+            continue;
+        }
+        if !in_package_table {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            if key.trim() == "name" {
+                let value = value.trim().trim_matches('"');
+                if !value.is_empty() {
+                    return value.to_string();
+                }
+            }
+        }
+    }
+
+    "program".to_string()
+}
+
+/// Locates the `Cargo.lock` that pins dependency versions for the program at `program_dir`.
+///
+/// A guest program is typically a workspace member, whose `Cargo.lock` lives at the workspace
+/// root rather than next to its own `Cargo.toml`, so this walks up through `program_dir`'s
+/// ancestors looking for the nearest one. Returns `None` if no ancestor has a `Cargo.lock`.
+pub(crate) fn resolve_cargo_lock_path(program_dir: &std::path::Path) -> Option<std::path::PathBuf> {
+    program_dir.ancestors().map(|dir| dir.join("Cargo.lock")).find(|path| path.is_file())
+}
+
+/// Resolves the Rust toolchain string (e.g. `"cargo 1.79.0 (...)"`) of the host's `cargo`, for
+/// recording in a [`crate::BuildManifest`]. For `--docker` builds this is not the cargo inside
+/// the container.
+///
+/// Falls back to `"unknown"` if `cargo --version` cannot be run, since a missing toolchain
+/// fingerprint shouldn't prevent the build itself from completing.
+pub(crate) fn resolve_toolchain() -> String {
+    Command::new("cargo")
+        .arg("--version")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
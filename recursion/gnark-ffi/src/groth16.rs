@@ -3,8 +3,9 @@ use std::{
     fs::File,
     io::Write,
     path::PathBuf,
-    process::{Command, Stdio},
-    time::Duration,
+    process::{Command, ExitStatus, Stdio},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
 use crate::witness::GnarkWitness;
@@ -17,10 +18,116 @@ use sp1_recursion_compiler::{
 };
 use std::thread;
 
+/// The protocol version spoken by this Rust client.
+///
+/// The first element is the major version: it must match the Gnark server's major version
+/// exactly, or the server is considered incompatible. The second element is the minor version:
+/// a mismatch here is only logged as a warning, since minor revisions are expected to stay
+/// backwards compatible.
+pub const PROTOCOL_VERSION: (u32, u32) = (1, 0);
+
+/// The backend this prover requires the Gnark server to advertise support for.
+pub const REQUIRED_BACKEND: &str = "groth16";
+
+/// The file [`Groth16Prover::build`] persists the target server's [`GnarkServerVersion`] to,
+/// alongside the circuit artifacts, for [`Groth16Prover::assert_artifact_compatible`] to later
+/// check a live server against.
+const BUILD_SERVER_VERSION_FILE: &str = "server_version_groth16.json";
+
+/// The version and capability information reported by the Gnark server's `/version` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GnarkServerVersion {
+    pub server_version: String,
+    pub protocol_version: (u32, u32),
+    pub supported_backends: Vec<String>,
+}
+
+/// An error encountered while starting up or talking to the Gnark server.
+#[derive(Debug)]
+pub enum GnarkServerError {
+    /// The Gnark server process failed to spawn.
+    Spawn(String),
+    /// The Gnark server process exited before it became healthy.
+    ServerExited(ExitStatus),
+    /// The server did not become healthy within `config.total_timeout`.
+    HealthCheckTimedOut,
+    /// The server did not become healthy within `config.max_attempts` polls.
+    HealthCheckAttemptsExhausted,
+    /// A request to the Gnark server failed.
+    Request(reqwest::Error),
+    /// The server's reported version or capabilities are incompatible with this client.
+    Incompatible(String),
+    /// The version persisted alongside a build artifact could not be parsed.
+    ArtifactVersion(serde_json::Error),
+}
+
+impl std::fmt::Display for GnarkServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Spawn(msg) => write!(f, "failed to spawn Gnark server: {msg}"),
+            Self::ServerExited(status) => {
+                write!(f, "Gnark server exited before becoming healthy: {status:?}")
+            }
+            Self::HealthCheckTimedOut => {
+                write!(f, "timed out waiting for Gnark server to become healthy")
+            }
+            Self::HealthCheckAttemptsExhausted => {
+                write!(f, "exhausted all poll attempts waiting for Gnark server to become healthy")
+            }
+            Self::Request(err) => write!(f, "request to Gnark server failed: {err}"),
+            Self::Incompatible(msg) => write!(f, "incompatible Gnark server: {msg}"),
+            Self::ArtifactVersion(err) => {
+                write!(f, "failed to parse build artifact's persisted server version: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GnarkServerError {}
+
+impl From<reqwest::Error> for GnarkServerError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Request(err)
+    }
+}
+
+/// How the spawned Gnark server process exited, observed from the thread supervising it.
+#[derive(Debug, Clone)]
+enum ChildOutcome {
+    SpawnFailed(String),
+    Exited(ExitStatus),
+}
+
+/// Tuning for [`wait_for_healthy_server`]'s retry loop.
+#[derive(Debug, Clone)]
+struct HealthCheckConfig {
+    /// The maximum number of poll attempts before giving up.
+    max_attempts: u32,
+    /// The total wall-clock budget for becoming healthy, independent of `max_attempts`.
+    total_timeout: Duration,
+    /// The initial delay between polls, doubled after each failed attempt up to `max_backoff`.
+    initial_backoff: Duration,
+    /// The maximum delay between polls.
+    max_backoff: Duration,
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 60,
+            total_timeout: Duration::from_secs(60),
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
 /// A prover that can generate proofs with the Groth16 protocol using bindings to Gnark.
 #[derive(Debug, Clone)]
 pub struct Groth16Prover {
     port: String,
+    /// The version reported by the Gnark server during the startup handshake.
+    pub server_version: GnarkServerVersion,
 }
 
 /// A zero-knowledge proof generated by the Groth16 protocol.
@@ -34,16 +141,31 @@ pub struct Groth16Proof {
 
 impl Groth16Prover {
     /// Starts up the Gnark server using Groth16 on the given port and waits for it to be ready.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the server fails to start, become healthy, or negotiate a compatible version.
+    /// Use [`Groth16Prover::try_new`] to handle these failures instead.
     pub fn new() -> Self {
+        Self::try_new().unwrap_or_else(|err| panic!("failed to start Gnark server: {err}"))
+    }
+
+    /// Starts up the Gnark server using Groth16 on the port from `HOST_PORT` (or a random port)
+    /// and waits for it to be ready, returning a typed error instead of panicking if the server process dies, never becomes
+    /// healthy within the configured timeout, or reports an incompatible version.
+    pub fn try_new() -> Result<Self, GnarkServerError> {
         let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         let gnark_dir = manifest_dir.join("../gnark");
         let port = env::var("HOST_PORT").unwrap_or_else(|_| generate_random_port().to_string());
         let port_clone = port.clone();
 
-        // Spawn a thread to run the command
+        let child_outcome: Arc<Mutex<Option<ChildOutcome>>> = Arc::new(Mutex::new(None));
+        let child_outcome_clone = child_outcome.clone();
+
+        // Spawn a thread to run the command and supervise its exit.
         // TODO: version by commit hash instead of by incrementing
         thread::spawn(move || {
-            let mut child = Command::new("go")
+            let child = Command::new("go")
                 .args([
                     "run",
                     "main.go",
@@ -59,47 +181,29 @@ impl Groth16Prover {
                 .stderr(Stdio::inherit())
                 .stdout(Stdio::inherit())
                 .stdin(Stdio::inherit())
-                .spawn()
-                .unwrap();
+                .spawn();
 
-            let exit_status = child.wait().unwrap();
+            let mut child = match child {
+                Ok(child) => child,
+                Err(err) => {
+                    *child_outcome_clone.lock().unwrap() =
+                        Some(ChildOutcome::SpawnFailed(err.to_string()));
+                    return;
+                }
+            };
 
-            if !exit_status.success() {
-                panic!("Gnark server exited with an error: {:?}", exit_status);
-            }
+            let outcome = match child.wait() {
+                Ok(status) => ChildOutcome::Exited(status),
+                Err(err) => ChildOutcome::SpawnFailed(err.to_string()),
+            };
+            *child_outcome_clone.lock().unwrap() = Some(outcome);
         });
 
-        let prover = Self { port: port_clone };
+        wait_for_healthy_server(&port_clone, &child_outcome, &HealthCheckConfig::default())?;
+        let server_version = fetch_server_version(&port_clone)?;
+        check_server_compatible(&server_version)?;
 
-        prover.wait_for_healthy_server().unwrap();
-
-        prover
-    }
-
-    /// Checks if the server is ready to accept requests.
-    fn wait_for_healthy_server(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let client = Client::new();
-        let url = format!("http://localhost:{}/healthz", self.port);
-
-        println!("Waiting for server to be healthy...");
-
-        loop {
-            match client.get(&url).send() {
-                Ok(response) => {
-                    if response.status() == StatusCode::OK {
-                        println!("Server is healthy!");
-                        return Ok(());
-                    } else {
-                        println!("Server is not healthy yet: {:?}", response.status());
-                    }
-                }
-                Err(err) => {
-                    println!("Server is not healthy yet: {:?}", err);
-                }
-            }
-
-            thread::sleep(Duration::from_secs(1));
-        }
+        Ok(Self { port: port_clone, server_version })
     }
 
     /// Executes the prover in testing mode with a circuit definition and witness.
@@ -158,7 +262,25 @@ impl Groth16Prover {
         }
     }
 
-    pub fn build<C: Config>(constraints: Vec<Constraint>, witness: Witness<C>, build_dir: PathBuf) {
+    /// Builds a Groth16 circuit artifact from the given constraints and witness.
+    ///
+    /// If `expected_server_version` is given, it is persisted alongside the artifact in
+    /// `build_dir`, so that a prover serving this artifact later can confirm via
+    /// [`Groth16Prover::assert_artifact_compatible`] that its own live server still matches the
+    /// one the artifact was built for. Pass the `server_version` of the [`Groth16Prover`] the
+    /// artifact is destined for, e.g. `Groth16Prover::build(..., Some(&prover.server_version))`.
+    pub fn build<C: Config>(
+        constraints: Vec<Constraint>,
+        witness: Witness<C>,
+        build_dir: PathBuf,
+        expected_server_version: Option<&GnarkServerVersion>,
+    ) {
+        if let Some(version) = expected_server_version {
+            let serialized = serde_json::to_string(version).unwrap();
+            let mut file = File::create(build_dir.join(BUILD_SERVER_VERSION_FILE)).unwrap();
+            file.write_all(serialized.as_bytes()).unwrap();
+        }
+
         let serialized = serde_json::to_string(&constraints).unwrap();
         let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         let gnark_dir = manifest_dir.join("../gnark");
@@ -209,6 +331,53 @@ impl Groth16Prover {
         }
     }
 
+    /// Checks that this prover's live server still matches the server version persisted by
+    /// [`Groth16Prover::build`] for the artifact in `build_dir`.
+    ///
+    /// Unlike `try_new`'s check against this client's own [`PROTOCOL_VERSION`], this compares two
+    /// independently observed values — the server version recorded at build time, and this
+    /// prover's actual `server_version` — so it can catch a real incompatibility: an artifact
+    /// built for one Gnark server later served by a different one (e.g. after an upgrade).
+    ///
+    /// Returns `Ok(())` if `build_dir` has no persisted version, since older artifacts predate
+    /// this check and must remain loadable.
+    pub fn assert_artifact_compatible(&self, build_dir: &std::path::Path) -> Result<(), GnarkServerError> {
+        let path = build_dir.join(BUILD_SERVER_VERSION_FILE);
+        let json = match std::fs::read_to_string(&path) {
+            Ok(json) => json,
+            Err(_) => return Ok(()),
+        };
+        let built_for: GnarkServerVersion =
+            serde_json::from_str(&json).map_err(GnarkServerError::ArtifactVersion)?;
+
+        if built_for.protocol_version.0 != self.server_version.protocol_version.0 {
+            return Err(GnarkServerError::Incompatible(format!(
+                "artifact in {} was built for server \"{}\" (protocol v{}.{}), but this prover's \
+                 server \"{}\" speaks v{}.{}",
+                build_dir.display(),
+                built_for.server_version,
+                built_for.protocol_version.0,
+                built_for.protocol_version.1,
+                self.server_version.server_version,
+                self.server_version.protocol_version.0,
+                self.server_version.protocol_version.1
+            )));
+        }
+
+        if !self.server_version.supported_backends.iter().any(|b| b == REQUIRED_BACKEND) {
+            return Err(GnarkServerError::Incompatible(format!(
+                "this prover's server \"{}\" no longer advertises support for the \"{}\" backend \
+                 the artifact in {} requires (supports: {:?})",
+                self.server_version.server_version,
+                REQUIRED_BACKEND,
+                build_dir.display(),
+                self.server_version.supported_backends
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Generates a Groth16 proof by sending a request to the Gnark server.
     pub fn prove<C: Config>(&self, witness: Witness<C>) -> Groth16Proof {
         let url = format!("http://localhost:{}/groth16/prove", self.port);
@@ -228,6 +397,239 @@ fn generate_random_port() -> u16 {
     rng.gen_range(1024..49152)
 }
 
+/// Polls the server's `/healthz` endpoint until it reports healthy, using exponential backoff
+/// with jitter between attempts.
+///
+/// Bails out early with a typed error if the supervised child process has already exited (or
+/// failed to spawn) rather than retrying against a server that will never come up. Fails with
+/// [`GnarkServerError::HealthCheckTimedOut`] if `config.total_timeout` elapses first, or with
+/// [`GnarkServerError::HealthCheckAttemptsExhausted`] if `config.max_attempts` polls all fail
+/// before the timeout does — these are reported as distinct errors since a 60s timeout hit after
+/// 3 slow attempts and 60 attempts hit in 2s are different failure modes worth telling apart.
+fn wait_for_healthy_server(
+    port: &str,
+    child_outcome: &Arc<Mutex<Option<ChildOutcome>>>,
+    config: &HealthCheckConfig,
+) -> Result<(), GnarkServerError> {
+    let client = Client::new();
+    let url = format!("http://localhost:{}/healthz", port);
+    let deadline = Instant::now() + config.total_timeout;
+    let mut backoff = config.initial_backoff;
+
+    println!("Waiting for server to be healthy...");
+
+    for attempt in 1..=config.max_attempts {
+        if let Some(outcome) = child_outcome.lock().unwrap().clone() {
+            return Err(match outcome {
+                ChildOutcome::SpawnFailed(msg) => GnarkServerError::Spawn(msg),
+                ChildOutcome::Exited(status) => GnarkServerError::ServerExited(status),
+            });
+        }
+
+        if Instant::now() >= deadline {
+            return Err(GnarkServerError::HealthCheckTimedOut);
+        }
+
+        match client.get(&url).send() {
+            Ok(response) if response.status() == StatusCode::OK => {
+                println!("Server is healthy!");
+                return Ok(());
+            }
+            Ok(response) => {
+                println!(
+                    "Server is not healthy yet (attempt {attempt}/{}): unexpected status {:?}",
+                    config.max_attempts,
+                    response.status()
+                );
+            }
+            Err(err) => {
+                println!(
+                    "Server is not healthy yet (attempt {attempt}/{}): connection failed: {err}",
+                    config.max_attempts
+                );
+            }
+        }
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(GnarkServerError::HealthCheckTimedOut);
+        }
+        let jitter = Duration::from_millis(rand::thread_rng().gen_range(0..100));
+        thread::sleep(backoff.min(remaining) + jitter);
+        backoff = (backoff * 2).min(config.max_backoff);
+    }
+
+    Err(GnarkServerError::HealthCheckAttemptsExhausted)
+}
+
+/// Fetches the Gnark server's reported version and capabilities from `/version`.
+fn fetch_server_version(port: &str) -> Result<GnarkServerVersion, GnarkServerError> {
+    let url = format!("http://localhost:{}/version", port);
+    let response = Client::new().get(&url).send()?.error_for_status()?;
+    let version: GnarkServerVersion = response.json()?;
+    Ok(version)
+}
+
+/// Checks that the server's reported version is compatible with what this client expects,
+/// returning a descriptive error on a major protocol mismatch or a missing required backend,
+/// and logging a warning on a minor protocol mismatch.
+fn check_server_compatible(version: &GnarkServerVersion) -> Result<(), GnarkServerError> {
+    if version.protocol_version.0 != PROTOCOL_VERSION.0 {
+        return Err(GnarkServerError::Incompatible(format!(
+            "client speaks protocol v{}.{}, server \"{}\" speaks v{}.{}",
+            PROTOCOL_VERSION.0,
+            PROTOCOL_VERSION.1,
+            version.server_version,
+            version.protocol_version.0,
+            version.protocol_version.1
+        )));
+    }
+
+    if version.protocol_version.1 != PROTOCOL_VERSION.1 {
+        println!(
+            "warning: Gnark server \"{}\" reports protocol v{}.{}, client expects v{}.{} (minor mismatch, continuing)",
+            version.server_version,
+            version.protocol_version.0,
+            version.protocol_version.1,
+            PROTOCOL_VERSION.0,
+            PROTOCOL_VERSION.1
+        );
+    }
+
+    if !version.supported_backends.iter().any(|b| b == REQUIRED_BACKEND) {
+        return Err(GnarkServerError::Incompatible(format!(
+            "\"{}\" does not advertise support for the \"{}\" backend (supports: {:?})",
+            version.server_version, REQUIRED_BACKEND, version.supported_backends
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(major: u32, minor: u32, backends: &[&str]) -> GnarkServerVersion {
+        GnarkServerVersion {
+            server_version: "test-server".to_string(),
+            protocol_version: (major, minor),
+            supported_backends: backends.iter().map(|b| b.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn accepts_matching_version_and_backend() {
+        let v = version(PROTOCOL_VERSION.0, PROTOCOL_VERSION.1, &[REQUIRED_BACKEND]);
+        assert!(check_server_compatible(&v).is_ok());
+    }
+
+    #[test]
+    fn accepts_minor_version_drift() {
+        let v = version(PROTOCOL_VERSION.0, PROTOCOL_VERSION.1 + 1, &[REQUIRED_BACKEND]);
+        assert!(check_server_compatible(&v).is_ok());
+    }
+
+    #[test]
+    fn rejects_major_version_mismatch() {
+        let v = version(PROTOCOL_VERSION.0 + 1, PROTOCOL_VERSION.1, &[REQUIRED_BACKEND]);
+        assert!(matches!(check_server_compatible(&v), Err(GnarkServerError::Incompatible(_))));
+    }
+
+    #[test]
+    fn rejects_missing_required_backend() {
+        let v = version(PROTOCOL_VERSION.0, PROTOCOL_VERSION.1, &["plonk"]);
+        assert!(matches!(check_server_compatible(&v), Err(GnarkServerError::Incompatible(_))));
+    }
+
+    #[test]
+    fn reports_dead_child_immediately_instead_of_retrying() {
+        let status = Command::new("true").status().unwrap();
+        let child_outcome = Arc::new(Mutex::new(Some(ChildOutcome::Exited(status))));
+        let config = HealthCheckConfig {
+            max_attempts: 100,
+            total_timeout: Duration::from_secs(60),
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        };
+
+        let result = wait_for_healthy_server("1", &child_outcome, &config);
+        assert!(matches!(result, Err(GnarkServerError::ServerExited(_))));
+    }
+
+    #[test]
+    fn reports_attempts_exhausted_distinctly_from_timeout() {
+        let child_outcome = Arc::new(Mutex::new(None));
+        let config = HealthCheckConfig {
+            max_attempts: 2,
+            total_timeout: Duration::from_secs(60),
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        };
+
+        // Port 1 is a reserved, unlisted port: connections to it fail immediately, so all
+        // `max_attempts` polls are exhausted well before `total_timeout` could fire.
+        let result = wait_for_healthy_server("1", &child_outcome, &config);
+        assert!(matches!(result, Err(GnarkServerError::HealthCheckAttemptsExhausted)));
+    }
+
+    #[test]
+    fn assert_artifact_compatible_accepts_matching_persisted_version() {
+        let dir = std::env::temp_dir()
+            .join(format!("sp1-gnark-test-accept-{:?}", std::thread::current().id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let built_for = version(PROTOCOL_VERSION.0, PROTOCOL_VERSION.1, &[REQUIRED_BACKEND]);
+        std::fs::write(
+            dir.join(BUILD_SERVER_VERSION_FILE),
+            serde_json::to_string(&built_for).unwrap(),
+        )
+        .unwrap();
+
+        let prover = Groth16Prover { port: "0".to_string(), server_version: built_for };
+        assert!(prover.assert_artifact_compatible(&dir).is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn assert_artifact_compatible_rejects_drifted_server() {
+        let dir = std::env::temp_dir()
+            .join(format!("sp1-gnark-test-reject-{:?}", std::thread::current().id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let built_for = version(PROTOCOL_VERSION.0, PROTOCOL_VERSION.1, &[REQUIRED_BACKEND]);
+        std::fs::write(
+            dir.join(BUILD_SERVER_VERSION_FILE),
+            serde_json::to_string(&built_for).unwrap(),
+        )
+        .unwrap();
+
+        let upgraded_server = version(PROTOCOL_VERSION.0 + 1, 0, &[REQUIRED_BACKEND]);
+        let prover = Groth16Prover { port: "0".to_string(), server_version: upgraded_server };
+        assert!(matches!(
+            prover.assert_artifact_compatible(&dir),
+            Err(GnarkServerError::Incompatible(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reports_timeout_distinctly_from_attempts_exhausted() {
+        let child_outcome = Arc::new(Mutex::new(None));
+        let config = HealthCheckConfig {
+            max_attempts: 1_000_000,
+            total_timeout: Duration::from_millis(1),
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        };
+
+        let result = wait_for_healthy_server("1", &child_outcome, &config);
+        assert!(matches!(result, Err(GnarkServerError::HealthCheckTimedOut)));
+    }
+}
+
 impl Default for Groth16Prover {
     fn default() -> Self {
         Self::new()